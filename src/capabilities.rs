@@ -0,0 +1,442 @@
+//! Typed representations of OGC `GetCapabilities` documents.
+//!
+//! WMS and WFS both answer a `GetCapabilities` request with an XML document
+//! describing what the service can do. The types here are the parsed,
+//! queryable form of those documents so callers can validate a layer name,
+//! CRS or bounding box before issuing a `GetMap`/`GetFeature` request instead
+//! of hand-copying strings from portal documentation.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::error::Error;
+
+/// A bounding box in some CRS, as reported by a capabilities document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox {
+    pub crs: String,
+    pub minx: f64,
+    pub miny: f64,
+    pub maxx: f64,
+    pub maxy: f64,
+}
+
+/// A named rendering style offered for a WMS layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub name: String,
+    pub title: String,
+}
+
+/// A node in the WMS `<Capability>/<Layer>` tree.
+///
+/// WMS layers inherit CRS, bounding box and styles from their parent unless
+/// they override them, so the fields below are always the fully-resolved
+/// (inherited) values for this node, not just what it declared itself.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Layer {
+    pub name: Option<String>,
+    pub title: String,
+    pub crs: Vec<String>,
+    pub bbox: Option<BoundingBox>,
+    pub styles: Vec<Style>,
+    pub queryable: bool,
+    pub children: Vec<Layer>,
+}
+
+impl Layer {
+    /// Find this layer or a descendant by name.
+    pub fn find(&self, name: &str) -> Option<&Layer> {
+        if self.name.as_deref() == Some(name) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+}
+
+/// Parsed `GetCapabilities` response for a WMS service.
+#[derive(Debug, Clone, Default)]
+pub struct WmsCapabilities {
+    pub service_title: String,
+    pub service_abstract: String,
+    pub root_layer: Layer,
+}
+
+impl WmsCapabilities {
+    /// Look up a layer by name anywhere in the capability tree.
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.root_layer.find(name)
+    }
+
+    /// Whether `layer` (honoring CRS inherited from its ancestors) supports `crs`.
+    pub fn supports_crs(&self, layer: &str, crs: &str) -> bool {
+        self.layer(layer)
+            .map(|l| l.crs.iter().any(|c| c.eq_ignore_ascii_case(crs)))
+            .unwrap_or(false)
+    }
+}
+
+/// A single feature type advertised by a WFS `GetCapabilities` document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FeatureType {
+    pub name: String,
+    pub default_crs: String,
+    pub other_crs: Vec<String>,
+    pub output_formats: Vec<String>,
+    pub wgs84_bbox: Option<BoundingBox>,
+}
+
+/// Parsed `GetCapabilities` response for a WFS service.
+#[derive(Debug, Clone, Default)]
+pub struct WfsCapabilities {
+    pub feature_types: Vec<FeatureType>,
+}
+
+impl WfsCapabilities {
+    /// Look up a feature type by its (qualified) name.
+    pub fn layer(&self, name: &str) -> Option<&FeatureType> {
+        self.feature_types.iter().find(|ft| ft.name == name)
+    }
+
+    /// Whether `layer` supports `crs`, either as its default or an alternate CRS.
+    pub fn supports_crs(&self, layer: &str, crs: &str) -> bool {
+        self.layer(layer)
+            .map(|ft| {
+                ft.default_crs.eq_ignore_ascii_case(crs)
+                    || ft.other_crs.iter().any(|c| c.eq_ignore_ascii_case(crs))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Strip an XML namespace prefix (e.g. `wms:Layer` -> `Layer`) for matching.
+fn local_name(tag: &[u8]) -> &[u8] {
+    match tag.iter().position(|&b| b == b':') {
+        Some(idx) => &tag[idx + 1..],
+        None => tag,
+    }
+}
+
+/// Parse a WMS `<BoundingBox>` element's attributes, regardless of whether
+/// quick-xml delivered it as `Event::Start` (has children, none of which we
+/// care about) or `Event::Empty` (self-closing, the common case).
+fn parse_bounding_box(e: &BytesStart) -> Result<BoundingBox, Box<dyn Error>> {
+    let mut crs = String::new();
+    let mut minx = 0.0;
+    let mut miny = 0.0;
+    let mut maxx = 0.0;
+    let mut maxy = 0.0;
+    for attr in e.attributes().flatten() {
+        let value = attr.unescape_value()?.into_owned();
+        match attr.key.as_ref() {
+            b"CRS" | b"SRS" => crs = value,
+            b"minx" => minx = value.parse().unwrap_or(0.0),
+            b"miny" => miny = value.parse().unwrap_or(0.0),
+            b"maxx" => maxx = value.parse().unwrap_or(0.0),
+            b"maxy" => maxy = value.parse().unwrap_or(0.0),
+            _ => {}
+        }
+    }
+    Ok(BoundingBox { crs, minx, miny, maxx, maxy })
+}
+
+fn read_text(reader: &mut Reader<&[u8]>) -> Result<String, Box<dyn Error>> {
+    let mut buf = Vec::new();
+    let text = match reader.read_event_into(&mut buf)? {
+        Event::Text(t) => t.unescape()?.into_owned(),
+        Event::CData(t) => String::from_utf8(t.into_inner().into_owned())?,
+        _ => String::new(),
+    };
+    Ok(text)
+}
+
+/// Parse a WMS `<Capability>/<Layer>` tree, propagating CRS/bbox/styles from
+/// parent layers down to children per the WMS inheritance rules.
+fn parse_layer(reader: &mut Reader<&[u8]>, inherited: &Layer) -> Result<Layer, Box<dyn Error>> {
+    let mut layer = inherited.clone();
+    layer.name = None;
+    layer.title.clear();
+    layer.children.clear();
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Name" => layer.name = Some(read_text(reader)?),
+                b"Title" => layer.title = read_text(reader)?,
+                b"CRS" | b"SRS" => {
+                    let crs = read_text(reader)?;
+                    if !layer.crs.iter().any(|c| c == &crs) {
+                        layer.crs.push(crs);
+                    }
+                }
+                b"BoundingBox" => layer.bbox = Some(parse_bounding_box(&e)?),
+                b"Style" => layer.styles.push(parse_style(reader)?),
+                b"Layer" => {
+                    let queryable = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == b"queryable")
+                        .map(|a| a.value.as_ref() == b"1")
+                        .unwrap_or(layer.queryable);
+                    let mut child = parse_layer(reader, &layer)?;
+                    child.queryable = queryable;
+                    layer.children.push(child);
+                }
+                _ => {}
+            },
+            // WMS servers commonly write BoundingBox (no children of its own)
+            // and a childless Layer self-closing; quick-xml delivers both as
+            // Event::Empty rather than a Start/End pair.
+            Event::Empty(e) if local_name(e.name().as_ref()) == b"BoundingBox" => {
+                layer.bbox = Some(parse_bounding_box(&e)?);
+            }
+            Event::Empty(e) if local_name(e.name().as_ref()) == b"Layer" => {
+                // Self-closing <Layer/>: no body to descend into, but it can
+                // still carry its own name/queryable attributes and must
+                // inherit CRS/bbox/styles from `layer`, not duplicate it.
+                // Name/Title are not inheritable WMS properties, so reset
+                // them before applying the element's own attributes, same as
+                // the nested Event::Start("Layer") path does.
+                let mut child = layer.clone();
+                child.name = None;
+                child.title.clear();
+                child.children.clear();
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value()?.into_owned();
+                    match attr.key.as_ref() {
+                        b"name" => child.name = Some(value),
+                        b"queryable" => child.queryable = value == "1",
+                        _ => {}
+                    }
+                }
+                layer.children.push(child);
+            }
+            Event::End(e) if local_name(e.name().as_ref()) == b"Layer" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(layer)
+}
+
+fn parse_style(reader: &mut Reader<&[u8]>) -> Result<Style, Box<dyn Error>> {
+    let mut style = Style { name: String::new(), title: String::new() };
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Name" => style.name = read_text(reader)?,
+                b"Title" => style.title = read_text(reader)?,
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"Style" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(style)
+}
+
+/// Parse a WMS `GetCapabilities` XML response.
+pub fn parse_wms_capabilities(xml: &str) -> Result<WmsCapabilities, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut caps = WmsCapabilities::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Title" => caps.service_title = read_text(&mut reader)?,
+                b"Abstract" => caps.service_abstract = read_text(&mut reader)?,
+                b"Layer" => {
+                    caps.root_layer = parse_layer(&mut reader, &Layer::default())?;
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(caps)
+}
+
+fn parse_feature_type(reader: &mut Reader<&[u8]>) -> Result<FeatureType, Box<dyn Error>> {
+    let mut ft = FeatureType::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Name" => ft.name = read_text(reader)?,
+                b"DefaultCRS" | b"DefaultSRS" => ft.default_crs = read_text(reader)?,
+                b"OtherCRS" | b"OtherSRS" => ft.other_crs.push(read_text(reader)?),
+                b"OutputFormats" => {}
+                b"Format" => ft.output_formats.push(read_text(reader)?),
+                b"WGS84BoundingBox" => ft.wgs84_bbox = Some(parse_wgs84_bbox(reader)?),
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"FeatureType" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(ft)
+}
+
+fn parse_wgs84_bbox(reader: &mut Reader<&[u8]>) -> Result<BoundingBox, Box<dyn Error>> {
+    let mut bbox = BoundingBox { crs: "EPSG:4326".to_string(), minx: 0.0, miny: 0.0, maxx: 0.0, maxy: 0.0 };
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"LowerCorner" => {
+                    let text = read_text(reader)?;
+                    let mut parts = text.split_whitespace();
+                    bbox.minx = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                    bbox.miny = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                }
+                b"UpperCorner" => {
+                    let text = read_text(reader)?;
+                    let mut parts = text.split_whitespace();
+                    bbox.maxx = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                    bbox.maxy = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                }
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"WGS84BoundingBox" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(bbox)
+}
+
+/// Parse a WFS `GetCapabilities` XML response.
+pub fn parse_wfs_capabilities(xml: &str) -> Result<WfsCapabilities, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut caps = WfsCapabilities::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if local_name(e.name().as_ref()) == b"FeatureType" => {
+                caps.feature_types.push(parse_feature_type(&mut reader)?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(caps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wms_capabilities_inherits_crs_bbox_and_styles_to_children() {
+        let xml = r#"<WMS_Capabilities>
+            <Capability>
+                <Layer>
+                    <Title>Root</Title>
+                    <CRS>EPSG:25832</CRS>
+                    <BoundingBox CRS="EPSG:25832" minx="0" miny="0" maxx="10" maxy="10"/>
+                    <Style><Name>default</Name><Title>Default</Title></Style>
+                    <Layer queryable="1">
+                        <Name>child</Name>
+                        <Title>Child</Title>
+                    </Layer>
+                </Layer>
+            </Capability>
+        </WMS_Capabilities>"#;
+
+        let caps = parse_wms_capabilities(xml).unwrap();
+        let child = caps.layer("child").unwrap();
+
+        assert_eq!(child.crs, vec!["EPSG:25832".to_string()]);
+        assert_eq!(
+            child.bbox,
+            Some(BoundingBox { crs: "EPSG:25832".to_string(), minx: 0.0, miny: 0.0, maxx: 10.0, maxy: 10.0 })
+        );
+        assert_eq!(child.styles, vec![Style { name: "default".to_string(), title: "Default".to_string() }]);
+        assert!(child.queryable);
+        assert!(caps.supports_crs("child", "EPSG:25832"));
+    }
+
+    #[test]
+    fn parse_wms_capabilities_self_closing_layer_inherits_template_and_reads_own_attributes() {
+        let xml = r#"<WMS_Capabilities>
+            <Capability>
+                <Layer>
+                    <Title>Root</Title>
+                    <CRS>EPSG:4326</CRS>
+                    <Layer name="sibling" queryable="1"/>
+                </Layer>
+            </Capability>
+        </WMS_Capabilities>"#;
+
+        let caps = parse_wms_capabilities(xml).unwrap();
+
+        assert_eq!(caps.root_layer.children.len(), 1);
+        let child = &caps.root_layer.children[0];
+        assert_eq!(child.name.as_deref(), Some("sibling"));
+        assert!(child.queryable);
+        assert_eq!(child.crs, vec!["EPSG:4326".to_string()]);
+        assert!(child.children.is_empty());
+    }
+
+    #[test]
+    fn parse_wms_capabilities_self_closing_layer_does_not_inherit_name_or_title() {
+        let xml = r#"<WMS_Capabilities>
+            <Capability>
+                <Layer>
+                    <Name>root</Name>
+                    <Title>Root</Title>
+                    <Layer/>
+                </Layer>
+            </Capability>
+        </WMS_Capabilities>"#;
+
+        let caps = parse_wms_capabilities(xml).unwrap();
+
+        let child = &caps.root_layer.children[0];
+        assert_eq!(child.name, None);
+        assert_eq!(child.title, "");
+    }
+
+    #[test]
+    fn parse_wfs_capabilities_reads_feature_type_crs_and_bbox() {
+        let xml = r#"<wfs:WFS_Capabilities>
+            <FeatureTypeList>
+                <FeatureType>
+                    <Name>ns:layer</Name>
+                    <DefaultCRS>urn:ogc:def:crs:EPSG::25832</DefaultCRS>
+                    <OtherCRS>EPSG:4326</OtherCRS>
+                    <WGS84BoundingBox>
+                        <LowerCorner>10.0 48.0</LowerCorner>
+                        <UpperCorner>11.0 49.0</UpperCorner>
+                    </WGS84BoundingBox>
+                </FeatureType>
+            </FeatureTypeList>
+        </wfs:WFS_Capabilities>"#;
+
+        let caps = parse_wfs_capabilities(xml).unwrap();
+        let ft = caps.layer("ns:layer").unwrap();
+
+        assert!(caps.supports_crs("ns:layer", "EPSG:4326"));
+        assert_eq!(
+            ft.wgs84_bbox,
+            Some(BoundingBox { crs: "EPSG:4326".to_string(), minx: 10.0, miny: 48.0, maxx: 11.0, maxy: 49.0 })
+        );
+    }
+}
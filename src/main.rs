@@ -2,59 +2,132 @@ use geo_types::Geometry;
 use geozero::ToGeo;
 use geozero::geojson::{GeoJson, GeoJsonReader, GeoJsonString};
 use reqwest::{self, Client, header};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::error::Error;
+use std::hash::{Hash, Hasher};
 
+mod capabilities;
+mod ogc;
+mod resolver;
 mod wms;
+mod wps;
 
-/// Authentication methods for WFS servers
-enum WfsAuth {
-    /// Basic HTTP authentication
-    Basic { username: String, password: String },
-    /// Token-based authentication
-    BearerToken(String),
-    /// API key in query parameter
-    ApiKey { param_name: String, key: String },
-    /// Cookie-based authentication
-    Cookie(String),
-}
+use capabilities::{WfsCapabilities, parse_wfs_capabilities};
+use ogc::{Auth, AuthLayer, LoginConfig, LoginCredentials, OgcService};
+use resolver::ServiceResolver;
 
 /// Client for accessing WFS services
-struct WfsClient {
+pub struct WfsClient {
     client: Client,
-    base_url: String,
-    auth: Option<WfsAuth>,
+    resolver: ServiceResolver,
+    auth: AuthLayer,
+}
+
+impl OgcService for WfsClient {
+    fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    fn auth_layer(&self) -> &AuthLayer {
+        &self.auth
+    }
 }
 
 impl WfsClient {
-    /// Create a new WFS client
-    pub fn new(base_url: &str, auth: Option<WfsAuth>) -> Result<Self, Box<dyn Error>> {
-        let mut headers = header::HeaderMap::new();
-        // Set common headers
-        headers.insert(
-            header::USER_AGENT,
-            header::HeaderValue::from_static("rust-wfs-client/0.1.0"),
-        );
+    /// Create a new WFS client over a single endpoint
+    pub fn new(base_url: &str, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        Self::with_mirrors(vec![base_url.to_string()], auth)
+    }
 
-        let client = Client::builder().default_headers(headers).build()?;
+    /// Create a new WFS client backed by a prioritized list of mirror
+    /// endpoints. Requests try each candidate in order, falling back to the
+    /// next on a connection error or non-success response.
+    pub fn with_mirrors(base_urls: Vec<String>, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wfs-client/0.1.0")?;
 
         Ok(WfsClient {
             client,
-            base_url: base_url.to_string(),
-            auth,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(auth),
         })
     }
 
-    /// Fetch features from the WFS server
+    /// Create a new WFS client that authenticates via a login endpoint
+    /// instead of a pre-shared credential. Calling `login` establishes the
+    /// session up front; otherwise the first `401`/`403` response triggers
+    /// it, and an expired session is re-established the same way.
+    pub fn with_login(
+        base_urls: Vec<String>,
+        login_url: String,
+        credentials: LoginCredentials,
+    ) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wfs-client/0.1.0")?;
+
+        Ok(WfsClient {
+            client,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(None).with_login(LoginConfig { login_url, credentials }),
+        })
+    }
+
+    /// Perform the login flow now rather than waiting for a `401`/`403` to
+    /// trigger it.
+    pub async fn login(&self, login_url: &str, credentials: &LoginCredentials) -> Result<(), Box<dyn Error>> {
+        OgcService::login(self, login_url, credentials).await
+    }
+
+    /// Fetch and parse the server's `GetCapabilities` document.
+    ///
+    /// Use this to discover feature type names, supported CRS and output
+    /// formats before calling `fetch_features`, instead of hand-copying
+    /// values from portal documentation.
+    pub async fn get_capabilities(&self) -> Result<WfsCapabilities, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.get_capabilities_from(base_url))
+            .await
+    }
+
+    async fn get_capabilities_from(&self, base_url: String) -> Result<WfsCapabilities, Box<dyn Error>> {
+        let url = format!("{}?service=WFS&version=2.0.0&request=GetCapabilities", base_url);
+
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "WFS GetCapabilities request failed with status: {}",
+                response.status()
+            )
+            .into());
+        }
+
+        let body = response.text().await?;
+        parse_wfs_capabilities(&body)
+    }
+
+    /// Fetch features from the WFS server, falling back across mirror
+    /// endpoints if one is unreachable or returns an error status.
     pub async fn fetch_features(
         &self,
         layer_name: &str,
         bbox: Option<&str>,
         max_features: Option<u32>,
+    ) -> Result<Vec<Geometry>, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.fetch_features_from(base_url, layer_name, bbox, max_features))
+            .await
+    }
+
+    async fn fetch_features_from(
+        &self,
+        base_url: String,
+        layer_name: &str,
+        bbox: Option<&str>,
+        max_features: Option<u32>,
     ) -> Result<Vec<Geometry>, Box<dyn Error>> {
         // Build base WFS request URL
         let mut url = format!(
             "{}?service=WFS&version=2.0.0&request=GetFeature&typeName={}&outputFormat=GEOJSON&srsname=EPSG:25832",
-            self.base_url, layer_name
+            base_url, layer_name
         );
 
         // Add optional parameters
@@ -66,35 +139,8 @@ impl WfsClient {
             url.push_str(&format!("&count={}", max));
         }
 
-        // Apply API key authentication if needed
-        let mut final_url = url.clone();
-        if let Some(WfsAuth::ApiKey { param_name, key }) = &self.auth {
-            final_url = format!("{}&{}={}", url, param_name, key);
-        }
-
-        // Build the request with appropriate authentication
-        let mut request = self.client.get(&final_url);
-
-        // Apply authentication if configured
-        if let Some(auth) = &self.auth {
-            match auth {
-                WfsAuth::Basic { username, password } => {
-                    request = request.basic_auth(username, Some(password));
-                }
-                WfsAuth::BearerToken(token) => {
-                    request = request.bearer_auth(token);
-                }
-                WfsAuth::Cookie(cookie_str) => {
-                    request = request.header(header::COOKIE, cookie_str);
-                }
-                WfsAuth::ApiKey { .. } => {
-                    // Already handled in URL construction
-                }
-            }
-        }
-
         // Execute request
-        let response = request.send().await?;
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
 
         // Check for success
         if !response.status().is_success() {
@@ -113,6 +159,224 @@ impl WfsClient {
 
         Ok(vec![geometries])
     }
+
+    /// Fetch every feature of a WFS 2.0 layer, transparently following
+    /// result paging with mirror failover applied per page.
+    ///
+    /// Prefers a server-advertised next page, taken from a `next` link in
+    /// the GeoJSON body (OGC API Features style) or a `Link: rel="next"`
+    /// response header, falling back to incrementing `startIndex` when
+    /// neither is present. Pages until there is no next page and no more
+    /// `startIndex` to try (the accumulated count reaches `numberMatched`,
+    /// or a page reports no features). Some servers ignore `startIndex` and
+    /// keep returning the first page; we detect that by hashing each page
+    /// body and aborting instead of looping forever.
+    ///
+    /// A server-advertised next link is tied to whichever mirror served the
+    /// page it came from, so it's rebased onto each candidate's origin
+    /// before being tried — failover applies to every page, not just the
+    /// first one.
+    pub async fn fetch_all_features(
+        &self,
+        layer_name: &str,
+        bbox: Option<&str>,
+        page_size: u32,
+    ) -> Result<Vec<Geometry>, Box<dyn Error>> {
+        let mut start_index = 0u32;
+        let mut next_url: Option<String> = None;
+        let mut number_matched: Option<u64> = None;
+        let mut last_page_hash: Option<u64> = None;
+        let mut all_geometries = Vec::new();
+
+        loop {
+            let (body, link_header) = match next_url.take() {
+                Some(url) => self.fetch_next_page_with_failover(&url).await?,
+                None => {
+                    self.fetch_page_with_failover(layer_name, bbox, page_size, start_index)
+                        .await?
+                }
+            };
+
+            let mut hasher = DefaultHasher::new();
+            body.hash(&mut hasher);
+            let page_hash = hasher.finish();
+            if last_page_hash == Some(page_hash) {
+                // Server ignored startIndex/next-link and handed back the same page again.
+                break;
+            }
+            last_page_hash = Some(page_hash);
+
+            let page = parse_feature_page(&body)?;
+            if number_matched.is_none() {
+                number_matched = page.number_matched;
+            }
+
+            if page.has_features {
+                all_geometries.push(GeoJsonString(body).to_geo()?);
+            }
+            // Count every page, including one reached through a next link, so
+            // a later startIndex fallback resumes after it instead of
+            // refetching pages already collected.
+            start_index += page_size;
+
+            let next = page
+                .next_link
+                .or_else(|| link_header.and_then(|h| parse_next_link_header(&h)));
+            if let Some(next) = next {
+                next_url = Some(next);
+                continue;
+            }
+
+            if !page.has_features || page.number_returned == Some(0) {
+                break;
+            }
+            if let Some(matched) = number_matched {
+                if u64::from(start_index) >= matched {
+                    break;
+                }
+            }
+        }
+
+        Ok(all_geometries)
+    }
+
+    async fn fetch_page_with_failover(
+        &self,
+        layer_name: &str,
+        bbox: Option<&str>,
+        page_size: u32,
+        start_index: u32,
+    ) -> Result<(String, Option<String>), Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| {
+                let url = build_page_url(&base_url, layer_name, bbox, page_size, start_index);
+                async move { self.fetch_page_at(&url).await }
+            })
+            .await
+    }
+
+    /// Fetch a server-advertised next page with mirror failover, by
+    /// rebasing `next_url` onto each candidate's origin in turn. Without
+    /// this, a next link (necessarily tied to the single mirror that
+    /// returned it) would abort the whole `fetch_all_features` call as
+    /// soon as that one mirror went down mid-sequence.
+    async fn fetch_next_page_with_failover(
+        &self,
+        next_url: &str,
+    ) -> Result<(String, Option<String>), Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| {
+                let url = rebase_next_url(next_url, &base_url).unwrap_or_else(|| next_url.to_string());
+                async move { self.fetch_page_at(&url).await }
+            })
+            .await
+    }
+
+    /// Fetch a single page from `url`, returning its body together with any
+    /// `Link` response header so the caller can follow a server-advertised
+    /// next page instead of guessing at `startIndex`.
+    async fn fetch_page_at(&self, url: &str) -> Result<(String, Option<String>), Box<dyn Error>> {
+        let response = self.send_with_reauth(self.authenticated_get(url)).await?;
+        if !response.status().is_success() {
+            return Err(format!("WFS request failed with status: {}", response.status()).into());
+        }
+
+        let link_header = response
+            .headers()
+            .get(header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+
+        Ok((body, link_header))
+    }
+}
+
+fn build_page_url(
+    base_url: &str,
+    layer_name: &str,
+    bbox: Option<&str>,
+    page_size: u32,
+    start_index: u32,
+) -> String {
+    let mut url = format!(
+        "{}?service=WFS&version=2.0.0&request=GetFeature&typeName={}&outputFormat=GEOJSON&srsname=EPSG:25832&count={}&startIndex={}",
+        base_url, layer_name, page_size, start_index
+    );
+
+    if let Some(b) = bbox {
+        url.push_str(&format!("&bbox={}", b));
+    }
+
+    url
+}
+
+/// Rebase `next_url` (an absolute URL returned by one mirror) onto
+/// `base_url`'s scheme/host/port, keeping `next_url`'s path and query
+/// intact. Returns `None` if either URL fails to parse.
+fn rebase_next_url(next_url: &str, base_url: &str) -> Option<String> {
+    let mut rebased = reqwest::Url::parse(next_url).ok()?;
+    let base = reqwest::Url::parse(base_url).ok()?;
+
+    rebased.set_scheme(base.scheme()).ok()?;
+    rebased.set_host(base.host_str()).ok()?;
+    rebased.set_port(base.port()).ok()?;
+
+    Some(rebased.to_string())
+}
+
+/// The paging-relevant fields of a single WFS 2.0 `GetFeature` page.
+///
+/// Kept separate from geometry extraction so a missing `numberReturned`
+/// (ArcGIS WFS GeoJSON commonly omits it) never hides features that are
+/// actually present in the body's `features` array.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct FeaturePage {
+    has_features: bool,
+    number_returned: Option<u64>,
+    number_matched: Option<u64>,
+    next_link: Option<String>,
+}
+
+fn parse_feature_page(body: &str) -> Result<FeaturePage, Box<dyn Error>> {
+    let json: serde_json::Value = serde_json::from_str(body)?;
+
+    let has_features = json
+        .get("features")
+        .and_then(|v| v.as_array())
+        .map(|features| !features.is_empty())
+        .unwrap_or(false);
+
+    let next_link = json
+        .get("links")
+        .and_then(|v| v.as_array())
+        .and_then(|links| {
+            links
+                .iter()
+                .find(|link| link.get("rel").and_then(|r| r.as_str()) == Some("next"))
+        })
+        .and_then(|link| link.get("href").and_then(|h| h.as_str()))
+        .map(str::to_string);
+
+    Ok(FeaturePage {
+        has_features,
+        number_returned: json.get("numberReturned").and_then(|v| v.as_u64()),
+        number_matched: json.get("numberMatched").and_then(|v| v.as_u64()),
+        next_link,
+    })
+}
+
+/// Pull a `rel="next"` URL out of an HTTP `Link` header value, e.g.
+/// `<https://example.com/wfs?startIndex=10>; rel="next"`.
+fn parse_next_link_header(value: &str) -> Option<String> {
+    value.split(',').find_map(|part| {
+        if !part.contains("rel=\"next\"") && !part.contains("rel=next") {
+            return None;
+        }
+        let start = part.find('<')?;
+        let end = part.find('>')?;
+        Some(part[start + 1..end].to_string())
+    })
 }
 
 #[tokio::main]
@@ -120,7 +384,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     /*// Example with basic auth
     let basic_auth_client = WfsClient::new(
         "https://secure-example.com/geoserver/wfs",
-        Some(WfsAuth::Basic {
+        Some(Auth::Basic {
             username: "username".to_string(),
             password: "password".to_string(),
         }),
@@ -128,6 +392,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let client = WfsClient::new("https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WFSServer", None)?;
 
+    // Discover feature type names, CRS and output formats instead of
+    // hand-copying them from portal documentation.
+    let capabilities = client.get_capabilities().await?;
+    println!("WFS capabilities: {:?}", capabilities);
+
     //pagingEnabled='true' preferCoordinatesForWfsT11='false' restrictToRequestBBOX='1' srsname='EPSG:25832' typename='LAGB_Geophysik_G1_OpenData:Isanomale_der_Bouguer-Schwerestörung__mGal_' url='https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WFSServer' version='auto'
 
     // Fetch features using one of the clients
@@ -141,5 +410,133 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     wms::fetch_wms_example().await?;
 
+    wps::fetch_wps_example().await?;
+
+    // OgcClient::from_scheme builds the right client for a scheme from a
+    // flat parameter map, rather than calling WfsClient/WmsClient/WpsClient
+    // directly, so config-driven callers can add a new service endpoint
+    // without matching on its protocol themselves.
+    for (scheme, base_url) in [
+        (
+            ogc::Scheme::Wfs,
+            "https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WFSServer",
+        ),
+        (
+            ogc::Scheme::Wms,
+            "https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WMSServer",
+        ),
+        (
+            ogc::Scheme::Wps,
+            "https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WFSServer/WpsServer",
+        ),
+    ] {
+        let mut params = HashMap::new();
+        params.insert("base_url".to_string(), base_url.to_string());
+        match ogc::OgcClient::from_scheme(scheme, &params)? {
+            ogc::OgcClient::Wfs(wfs_client) => {
+                let capabilities = wfs_client.get_capabilities().await?;
+                println!("OgcClient-constructed WFS capabilities: {:?}", capabilities);
+            }
+            ogc::OgcClient::Wms(wms_client) => {
+                let capabilities = wms_client.get_capabilities().await?;
+                println!("OgcClient-constructed WMS capabilities: {:?}", capabilities);
+            }
+            ogc::OgcClient::Wps(wps_client) => {
+                let processes = wps_client.get_capabilities().await?;
+                println!("OgcClient-constructed WPS processes: {:?}", processes);
+            }
+        }
+    }
+
+    // Example of a portal that requires logging in before serving requests:
+    // `login` can be called up front; otherwise the first 401/403 triggers
+    // it, and an expired session is re-established the same way.
+    let _login_client = WfsClient::with_login(
+        vec!["https://secure-example.com/geoserver/wfs".to_string()],
+        "https://secure-example.com/login".to_string(),
+        LoginCredentials::Form(HashMap::from([
+            ("username".to_string(), "demo".to_string()),
+            ("password".to_string(), "demo".to_string()),
+        ])),
+    )?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feature_page_extracts_features_without_number_returned() {
+        // ArcGIS WFS GeoJSON commonly omits numberReturned/numberMatched entirely.
+        let body = r#"{
+            "type": "FeatureCollection",
+            "features": [{"type": "Feature", "geometry": {"type": "Point", "coordinates": [1.0, 2.0]}, "properties": {}}]
+        }"#;
+
+        let page = parse_feature_page(body).unwrap();
+        assert!(page.has_features);
+        assert_eq!(page.number_returned, None);
+        assert_eq!(page.number_matched, None);
+        assert_eq!(page.next_link, None);
+    }
+
+    #[test]
+    fn parse_feature_page_reports_no_features_on_empty_array() {
+        let body = r#"{"type": "FeatureCollection", "features": [], "numberReturned": 0}"#;
+
+        let page = parse_feature_page(body).unwrap();
+        assert!(!page.has_features);
+        assert_eq!(page.number_returned, Some(0));
+    }
+
+    #[test]
+    fn parse_feature_page_reads_next_link_from_body() {
+        let body = r#"{
+            "type": "FeatureCollection",
+            "features": [],
+            "links": [{"rel": "self", "href": "https://example.com/wfs?startIndex=0"},
+                      {"rel": "next", "href": "https://example.com/wfs?startIndex=10"}]
+        }"#;
+
+        let page = parse_feature_page(body).unwrap();
+        assert_eq!(
+            page.next_link.as_deref(),
+            Some("https://example.com/wfs?startIndex=10")
+        );
+    }
+
+    #[test]
+    fn parse_next_link_header_extracts_rel_next() {
+        let value = r#"<https://example.com/wfs?startIndex=10>; rel="next", <https://example.com/wfs?startIndex=0>; rel="prev""#;
+
+        assert_eq!(
+            parse_next_link_header(value).as_deref(),
+            Some("https://example.com/wfs?startIndex=10")
+        );
+    }
+
+    #[test]
+    fn parse_next_link_header_returns_none_without_next() {
+        let value = r#"<https://example.com/wfs?startIndex=0>; rel="prev""#;
+
+        assert_eq!(parse_next_link_header(value), None);
+    }
+
+    #[test]
+    fn rebase_next_url_swaps_origin_but_keeps_path_and_query() {
+        let next = "https://mirror-a.example.com/wfs?startIndex=10";
+        let base = "https://mirror-b.example.com:8443/wfs";
+
+        assert_eq!(
+            rebase_next_url(next, base).as_deref(),
+            Some("https://mirror-b.example.com:8443/wfs?startIndex=10")
+        );
+    }
+
+    #[test]
+    fn rebase_next_url_returns_none_on_unparsable_input() {
+        assert_eq!(rebase_next_url("not a url", "https://mirror-b.example.com"), None);
+    }
+}
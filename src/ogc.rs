@@ -0,0 +1,487 @@
+//! Shared plumbing for the WMS/WFS/WPS clients.
+//!
+//! Each protocol client used to define its own near-identical auth enum and
+//! repeat the same "apply API key to the URL, apply everything else to the
+//! request builder" match. `Auth`/`AuthLayer` collapse that into one place,
+//! `OgcService` is the common surface every client implements, and
+//! `OgcClient::from_scheme` is a factory so a new protocol can be added
+//! without duplicating the auth/URL-assembly boilerplate again.
+
+use reqwest::{Client, RequestBuilder, Response, header};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Authentication method shared by all OGC clients (WMS, WFS, WPS, ...).
+#[derive(Clone)]
+pub enum Auth {
+    /// Basic HTTP authentication
+    Basic { username: String, password: String },
+    /// Token-based authentication
+    BearerToken(String),
+    /// API key in query parameter
+    ApiKey { param_name: String, key: String },
+    /// Cookie-based authentication
+    Cookie(String),
+}
+
+/// Credentials submitted to a portal's login endpoint.
+pub enum LoginCredentials {
+    /// Submitted as `application/x-www-form-urlencoded`.
+    Form(HashMap<String, String>),
+    /// Submitted as a JSON body.
+    Json(serde_json::Value),
+}
+
+/// A login endpoint and the credentials to post to it, used to establish
+/// (and, on expiry, re-establish) an authenticated session.
+pub struct LoginConfig {
+    pub login_url: String,
+    pub credentials: LoginCredentials,
+}
+
+/// Applies an [`Auth`] to outgoing requests: API keys go in the query
+/// string, everything else is applied as a header on the request builder.
+///
+/// Also carries the state for session-based auth: once [`OgcService::login`]
+/// succeeds, any bearer token found in the login response is cached here and
+/// takes priority over a static `Auth`, while a session cookie is captured
+/// automatically by the client's cookie store.
+pub struct AuthLayer {
+    auth: Option<Auth>,
+    session_token: Mutex<Option<String>>,
+    login: Option<LoginConfig>,
+}
+
+impl AuthLayer {
+    pub fn new(auth: Option<Auth>) -> Self {
+        AuthLayer {
+            auth,
+            session_token: Mutex::new(None),
+            login: None,
+        }
+    }
+
+    /// Attach a login endpoint so an expired session can be transparently
+    /// re-established by [`OgcService::send_with_reauth`].
+    pub fn with_login(mut self, login: LoginConfig) -> Self {
+        self.login = Some(login);
+        self
+    }
+
+    pub(crate) fn login_config(&self) -> Option<&LoginConfig> {
+        self.login.as_ref()
+    }
+
+    pub(crate) fn set_session_token(&self, token: Option<String>) {
+        *self.session_token.lock().unwrap() = token;
+    }
+
+    /// Append the API-key query parameter to `url`, if configured.
+    pub fn apply_to_url(&self, url: &str) -> String {
+        match &self.auth {
+            Some(Auth::ApiKey { param_name, key }) => {
+                let separator = if url.contains('?') { '&' } else { '?' };
+                format!("{}{}{}={}", url, separator, param_name, key)
+            }
+            _ => url.to_string(),
+        }
+    }
+
+    /// Apply authentication to a request builder: a cached session token
+    /// takes priority (if `login` has run), otherwise the static `Auth` is
+    /// used. API keys are handled by `apply_to_url` instead, since they
+    /// live in the query string rather than a header.
+    pub fn apply_to_request(&self, request: RequestBuilder) -> RequestBuilder {
+        if let Some(token) = self.session_token.lock().unwrap().clone() {
+            return request.bearer_auth(token);
+        }
+
+        match &self.auth {
+            Some(Auth::Basic { username, password }) => request.basic_auth(username, Some(password)),
+            Some(Auth::BearerToken(token)) => request.bearer_auth(token),
+            Some(Auth::Cookie(cookie_str)) => request.header(header::COOKIE, cookie_str),
+            Some(Auth::ApiKey { .. }) | None => request,
+        }
+    }
+}
+
+/// Build the `reqwest::Client` every OGC client sends requests through,
+/// tagged with its own `User-Agent` and keeping a cookie jar so a session
+/// cookie returned by `login` is reused automatically on later requests.
+pub fn build_http_client(user_agent: &'static str) -> Result<Client, Box<dyn Error>> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::USER_AGENT, header::HeaderValue::from_static(user_agent));
+    Ok(Client::builder()
+        .default_headers(headers)
+        .cookie_store(true)
+        .build()?)
+}
+
+/// Shared behavior of an OGC protocol client: the request plumbing every
+/// client needs, independent of its protocol-specific operations (`GetMap`,
+/// `GetFeature`, `Execute`, ...).
+pub trait OgcService {
+    /// The HTTP client used to issue requests.
+    fn http_client(&self) -> &Client;
+
+    /// The authentication layer applied to every request.
+    fn auth_layer(&self) -> &AuthLayer;
+
+    /// Build a GET request against `url`, with API-key auth applied.
+    ///
+    /// Header-based auth (bearer/basic/cookie) is deliberately left for
+    /// `send_with_reauth` to apply, not applied here: it must be re-derived
+    /// from the current `AuthLayer` state on every send attempt, so a retry
+    /// after `login()` refreshes the session token picks up the new one
+    /// instead of carrying the stale header forward.
+    fn authenticated_get(&self, url: &str) -> RequestBuilder {
+        let final_url = self.auth_layer().apply_to_url(url);
+        self.http_client().get(final_url)
+    }
+
+    /// Build a POST request against `url` with `body`, with API-key auth
+    /// applied. See `authenticated_get` for why header-based auth is left
+    /// to `send_with_reauth`.
+    fn authenticated_post(&self, url: &str, content_type: &'static str, body: String) -> RequestBuilder {
+        let final_url = self.auth_layer().apply_to_url(url);
+        self.http_client()
+            .post(final_url)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body)
+    }
+
+    /// Post `credentials` to `login_url` to establish an authenticated
+    /// session. A session cookie in the response is captured automatically
+    /// by the client's cookie store; a `token`/`access_token` field in a
+    /// JSON response body is cached for bearer authentication on later
+    /// requests.
+    async fn login(&self, login_url: &str, credentials: &LoginCredentials) -> Result<(), Box<dyn Error>> {
+        let request = match credentials {
+            LoginCredentials::Form(fields) => self.http_client().post(login_url).form(fields),
+            LoginCredentials::Json(body) => self.http_client().post(login_url).json(body),
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "login request to {} failed with status: {}",
+                login_url,
+                response.status()
+            )
+            .into());
+        }
+
+        if let Ok(body) = response.json::<serde_json::Value>().await {
+            let token = body
+                .get("token")
+                .or_else(|| body.get("access_token"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            if token.is_some() {
+                self.auth_layer().set_session_token(token);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send `request` (as returned by `authenticated_get`/`authenticated_post`,
+    /// i.e. with header-based auth not yet applied), and if the server
+    /// answers `401`/`403` and a login endpoint is configured, log in again
+    /// and retry the request once. This lets a long-running harvest job
+    /// survive session expiry without the caller rebuilding the client.
+    ///
+    /// The request is cloned *before* auth is applied, and auth is applied
+    /// fresh to each of the original send and the retry, so a refreshed
+    /// session token from `login()` replaces the stale one on retry instead
+    /// of being appended alongside it.
+    async fn send_with_reauth(&self, request: RequestBuilder) -> Result<Response, Box<dyn Error>> {
+        let retry = request.try_clone();
+        let response = self.auth_layer().apply_to_request(request).send().await?;
+
+        if matches!(response.status().as_u16(), 401 | 403) {
+            if let (Some(retry), Some(login)) = (retry, self.auth_layer().login_config()) {
+                self.login(&login.login_url, &login.credentials).await?;
+                let retried = self.auth_layer().apply_to_request(retry);
+                return Ok(retried.send().await?);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// Which OGC service a [`Scheme`] + parameter map should construct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Wms,
+    Wfs,
+    Wps,
+}
+
+/// A client for one of the supported OGC service types, constructed
+/// uniformly via [`OgcClient::from_scheme`].
+pub enum OgcClient {
+    Wms(crate::wms::WmsClient),
+    Wfs(crate::WfsClient),
+    Wps(crate::wps::WpsClient),
+}
+
+impl OgcClient {
+    /// Construct the right client for `scheme` from a flat parameter map,
+    /// so new service types can be registered without duplicating the
+    /// authentication and URL-assembly boilerplate.
+    ///
+    /// Recognized params: `base_url` (required; comma-separated for
+    /// mirrors), and at most one of `username`+`password`, `bearer_token`,
+    /// `api_key_param`+`api_key`, or `cookie`.
+    pub fn from_scheme(scheme: Scheme, params: &HashMap<String, String>) -> Result<OgcClient, Box<dyn Error>> {
+        let base_urls: Vec<String> = params
+            .get("base_url")
+            .ok_or("missing required param: base_url")?
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect();
+
+        let auth = auth_from_params(params);
+
+        Ok(match scheme {
+            Scheme::Wms => OgcClient::Wms(crate::wms::WmsClient::with_mirrors(base_urls, auth)?),
+            Scheme::Wfs => OgcClient::Wfs(crate::WfsClient::with_mirrors(base_urls, auth)?),
+            Scheme::Wps => OgcClient::Wps(crate::wps::WpsClient::with_mirrors(base_urls, auth)?),
+        })
+    }
+}
+
+fn auth_from_params(params: &HashMap<String, String>) -> Option<Auth> {
+    if let (Some(username), Some(password)) = (params.get("username"), params.get("password")) {
+        return Some(Auth::Basic {
+            username: username.clone(),
+            password: password.clone(),
+        });
+    }
+    if let Some(token) = params.get("bearer_token") {
+        return Some(Auth::BearerToken(token.clone()));
+    }
+    if let (Some(param_name), Some(key)) = (params.get("api_key_param"), params.get("api_key")) {
+        return Some(Auth::ApiKey {
+            param_name: param_name.clone(),
+            key: key.clone(),
+        });
+    }
+    if let Some(cookie) = params.get("cookie") {
+        return Some(Auth::Cookie(cookie.clone()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct TestService {
+        client: Client,
+        auth: AuthLayer,
+    }
+
+    impl OgcService for TestService {
+        fn http_client(&self) -> &Client {
+            &self.client
+        }
+
+        fn auth_layer(&self) -> &AuthLayer {
+            &self.auth
+        }
+    }
+
+    #[test]
+    fn apply_to_request_prefers_session_token_over_static_auth() {
+        let auth = AuthLayer::new(Some(Auth::Basic {
+            username: "u".to_string(),
+            password: "p".to_string(),
+        }));
+        auth.set_session_token(Some("session-token".to_string()));
+
+        let client = Client::new();
+        let request = auth.apply_to_request(client.get("https://example.com")).build().unwrap();
+
+        let header = request.headers().get(header::AUTHORIZATION).unwrap().to_str().unwrap();
+        assert_eq!(header, "Bearer session-token");
+    }
+
+    #[test]
+    fn apply_to_request_falls_back_to_static_auth_without_session_token() {
+        let auth = AuthLayer::new(Some(Auth::BearerToken("static-token".to_string())));
+
+        let client = Client::new();
+        let request = auth.apply_to_request(client.get("https://example.com")).build().unwrap();
+
+        let header = request.headers().get(header::AUTHORIZATION).unwrap().to_str().unwrap();
+        assert_eq!(header, "Bearer static-token");
+    }
+
+    #[test]
+    fn apply_to_url_appends_api_key_only_for_api_key_auth() {
+        let auth = AuthLayer::new(Some(Auth::ApiKey {
+            param_name: "key".to_string(),
+            key: "abc123".to_string(),
+        }));
+
+        assert_eq!(auth.apply_to_url("https://example.com?a=1"), "https://example.com?a=1&key=abc123");
+
+        let no_api_key = AuthLayer::new(Some(Auth::BearerToken("t".to_string())));
+        assert_eq!(no_api_key.apply_to_url("https://example.com"), "https://example.com");
+    }
+
+    /// A canned status/body pair returned by `spawn_mock_server`'s next queued request.
+    struct MockResponse {
+        status: u16,
+        body: String,
+    }
+
+    /// Serve `responses` in order off a background thread, one per accepted
+    /// connection, recording the raw request text (headers included) it read
+    /// for each. Returns the server's base URL and the shared log of raw
+    /// requests received so far.
+    fn spawn_mock_server(responses: Vec<MockResponse>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let captured_in_thread = captured.clone();
+
+        thread::spawn(move || {
+            let mut responses = responses.into_iter();
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut buf = [0u8; 8192];
+                let Ok(n) = stream.read(&mut buf) else { continue };
+                captured_in_thread
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buf[..n]).to_string());
+
+                let response = responses.next().unwrap_or(MockResponse {
+                    status: 404,
+                    body: String::new(),
+                });
+                let status_line = match response.status {
+                    200 => "200 OK",
+                    401 => "401 Unauthorized",
+                    _ => "404 Not Found",
+                };
+                let payload = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{}",
+                    status_line,
+                    response.body.len(),
+                    response.body
+                );
+                let _ = stream.write_all(payload.as_bytes());
+            }
+        });
+
+        (format!("http://{}", addr), captured)
+    }
+
+    #[tokio::test]
+    async fn send_with_reauth_refreshes_token_without_duplicating_auth_header() {
+        let (base_url, captured) = spawn_mock_server(vec![
+            MockResponse {
+                status: 401,
+                body: String::new(),
+            },
+            MockResponse {
+                status: 200,
+                body: r#"{"token":"new-token"}"#.to_string(),
+            },
+            MockResponse {
+                status: 200,
+                body: "ok".to_string(),
+            },
+        ]);
+
+        let auth = AuthLayer::new(None).with_login(LoginConfig {
+            login_url: format!("{base_url}/login"),
+            credentials: LoginCredentials::Json(serde_json::json!({})),
+        });
+        // Simulate a session that already authenticated once and is now stale.
+        auth.set_session_token(Some("old-token".to_string()));
+
+        let service = TestService {
+            client: build_http_client("ogc-test-client/0.1").unwrap(),
+            auth,
+        };
+
+        let response = service
+            .send_with_reauth(service.authenticated_get(&format!("{base_url}/resource")))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 3, "expected initial GET, login POST, retried GET");
+
+        let retried_get = &requests[2];
+        let auth_header_count = retried_get
+            .lines()
+            .filter(|line| line.to_ascii_lowercase().starts_with("authorization:"))
+            .count();
+        assert_eq!(
+            auth_header_count, 1,
+            "retried request must carry exactly one Authorization header, got:\n{retried_get}"
+        );
+        assert!(retried_get.contains("Bearer new-token"));
+        assert!(!retried_get.contains("old-token"));
+    }
+
+    #[tokio::test]
+    async fn send_with_reauth_passes_through_401_without_login_config() {
+        let (base_url, captured) = spawn_mock_server(vec![MockResponse {
+            status: 401,
+            body: String::new(),
+        }]);
+
+        let service = TestService {
+            client: build_http_client("ogc-test-client/0.1").unwrap(),
+            auth: AuthLayer::new(Some(Auth::BearerToken("token".to_string()))),
+        };
+
+        let response = service
+            .send_with_reauth(service.authenticated_get(&format!("{base_url}/resource")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 401);
+        assert_eq!(captured.lock().unwrap().len(), 1, "no login config means no retry");
+    }
+
+    #[tokio::test]
+    async fn login_sets_session_token_from_json_response() {
+        let (base_url, _captured) = spawn_mock_server(vec![MockResponse {
+            status: 200,
+            body: r#"{"access_token":"abc"}"#.to_string(),
+        }]);
+
+        let service = TestService {
+            client: build_http_client("ogc-test-client/0.1").unwrap(),
+            auth: AuthLayer::new(None),
+        };
+
+        service
+            .login(&format!("{base_url}/login"), &LoginCredentials::Json(serde_json::json!({})))
+            .await
+            .unwrap();
+
+        let request = service
+            .auth_layer()
+            .apply_to_request(service.client.get("https://example.com"))
+            .build()
+            .unwrap();
+        let header = request.headers().get(header::AUTHORIZATION).unwrap().to_str().unwrap();
+        assert_eq!(header, "Bearer abc");
+    }
+}
@@ -0,0 +1,178 @@
+//! Failover across a prioritized list of mirror endpoints.
+//!
+//! Real geoportals often expose the same dataset behind several mirror
+//! URLs that intermittently fail. `ServiceResolver` holds those candidates
+//! in priority order and remembers the last one that worked, so a caller
+//! can try each endpoint in turn without restarting from the top of the
+//! list (and dead mirrors first) on every request.
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+
+/// Why one candidate endpoint failed to serve a request.
+#[derive(Debug, Clone)]
+pub struct EndpointFailure {
+    pub url: String,
+    pub reason: String,
+}
+
+/// Every candidate endpoint failed; carries the reason for each.
+#[derive(Debug)]
+pub struct ResolutionError {
+    pub failures: Vec<EndpointFailure>,
+}
+
+impl fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "all {} candidate endpoint(s) failed:", self.failures.len())?;
+        for failure in &self.failures {
+            write!(f, " [{}: {}]", failure.url, failure.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ResolutionError {}
+
+/// A prioritized list of candidate base URLs for the same service.
+pub struct ServiceResolver {
+    candidates: Vec<String>,
+    last_good: Mutex<Option<usize>>,
+}
+
+impl ServiceResolver {
+    /// Create a resolver over `candidates`, tried in the given order.
+    pub fn new(candidates: Vec<String>) -> Self {
+        ServiceResolver {
+            candidates,
+            last_good: Mutex::new(None),
+        }
+    }
+
+    /// Candidate base URLs in the order they should be tried: the
+    /// last-known-good endpoint first (if any), then the rest in priority
+    /// order.
+    fn ordered_candidates(&self) -> Vec<String> {
+        let n = self.candidates.len();
+        let start = self.last_good.lock().unwrap().unwrap_or(0);
+        (0..n)
+            .map(|offset| self.candidates[(start + offset) % n].clone())
+            .collect()
+    }
+
+    /// Remember `url` as the endpoint to try first next time.
+    fn mark_good(&self, url: &str) {
+        if let Some(idx) = self.candidates.iter().position(|c| c == url) {
+            *self.last_good.lock().unwrap() = Some(idx);
+        }
+    }
+
+    /// Try `attempt` against each candidate endpoint in priority order
+    /// (last-known-good first), returning the first success and remembering
+    /// it as last-known-good for next time. If every candidate fails,
+    /// returns a [`ResolutionError`] carrying each one's failure reason.
+    ///
+    /// This is the one place the failover loop is written; callers that
+    /// need an operation to run across mirrors should go through here
+    /// instead of reimplementing the loop.
+    pub async fn try_each<T, F, Fut>(&self, mut attempt: F) -> Result<T, Box<dyn Error>>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn Error>>>,
+    {
+        let mut failures = Vec::new();
+        for base_url in self.ordered_candidates() {
+            match attempt(base_url.clone()).await {
+                Ok(value) => {
+                    self.mark_good(&base_url);
+                    return Ok(value);
+                }
+                Err(e) => failures.push(EndpointFailure {
+                    url: base_url,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        Err(Box::new(ResolutionError { failures }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn try_each_returns_first_success() {
+        let resolver = ServiceResolver::new(vec!["https://a".to_string(), "https://b".to_string()]);
+
+        let result = resolver
+            .try_each(|url| async move { Ok::<_, Box<dyn Error>>(url) })
+            .await
+            .unwrap();
+
+        assert_eq!(result, "https://a");
+    }
+
+    #[tokio::test]
+    async fn try_each_falls_back_to_next_candidate_on_error() {
+        let resolver = ServiceResolver::new(vec!["https://a".to_string(), "https://b".to_string()]);
+
+        let result = resolver
+            .try_each(|url| async move {
+                if url == "https://a" {
+                    Err::<String, Box<dyn Error>>("connection refused".into())
+                } else {
+                    Ok(url)
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result, "https://b");
+    }
+
+    #[tokio::test]
+    async fn try_each_remembers_last_good_candidate() {
+        let resolver = ServiceResolver::new(vec!["https://a".to_string(), "https://b".to_string()]);
+
+        resolver
+            .try_each(|url| async move {
+                if url == "https://a" {
+                    Err::<String, Box<dyn Error>>("down".into())
+                } else {
+                    Ok(url)
+                }
+            })
+            .await
+            .unwrap();
+
+        let attempts = AtomicUsize::new(0);
+        let result = resolver
+            .try_each(|url| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, Box<dyn Error>>(url) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result, "https://b");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn try_each_reports_every_failure_when_all_candidates_fail() {
+        let resolver = ServiceResolver::new(vec!["https://a".to_string(), "https://b".to_string()]);
+
+        let err = resolver
+            .try_each(|url| async move { Err::<(), Box<dyn Error>>(format!("{url} unreachable").into()) })
+            .await
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("https://a"));
+        assert!(message.contains("https://b"));
+    }
+}
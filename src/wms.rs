@@ -1,48 +1,106 @@
-use reqwest::{self, Client, header};
+use reqwest::{self, Client};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
-/// Authentication methods for WMS servers (reusing from your WFS implementation)
-enum WmsAuth {
-    /// Basic HTTP authentication
-    Basic { username: String, password: String },
-    /// Token-based authentication
-    BearerToken(String),
-    /// API key in query parameter
-    ApiKey { param_name: String, key: String },
-    /// Cookie-based authentication
-    Cookie(String),
-}
+use crate::capabilities::{WmsCapabilities, parse_wms_capabilities};
+use crate::ogc::{self, Auth, AuthLayer, LoginConfig, LoginCredentials, OgcService};
+use crate::resolver::ServiceResolver;
 
 /// Client for accessing WMS services
-struct WmsClient {
+pub struct WmsClient {
     client: Client,
-    base_url: String,
-    auth: Option<WmsAuth>,
+    resolver: ServiceResolver,
+    auth: AuthLayer,
+}
+
+impl OgcService for WmsClient {
+    fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    fn auth_layer(&self) -> &AuthLayer {
+        &self.auth
+    }
 }
 
 impl WmsClient {
-    /// Create a new WMS client
-    pub fn new(base_url: &str, auth: Option<WmsAuth>) -> Result<Self, Box<dyn Error>> {
-        let mut headers = header::HeaderMap::new();
-        // Set common headers
-        headers.insert(
-            header::USER_AGENT,
-            header::HeaderValue::from_static("rust-wms-client/0.1.0"),
-        );
+    /// Create a new WMS client over a single endpoint
+    pub fn new(base_url: &str, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        Self::with_mirrors(vec![base_url.to_string()], auth)
+    }
 
-        let client = Client::builder().default_headers(headers).build()?;
+    /// Create a new WMS client backed by a prioritized list of mirror
+    /// endpoints. Requests try each candidate in order, falling back to the
+    /// next on a connection error or non-success response.
+    pub fn with_mirrors(base_urls: Vec<String>, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wms-client/0.1.0")?;
 
         Ok(WmsClient {
             client,
-            base_url: base_url.to_string(),
-            auth,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(auth),
         })
     }
 
-    /// Fetch a map tile from WMS server as PNG
+    /// Create a new WMS client that authenticates via a login endpoint
+    /// instead of a pre-shared credential. Calling `login` establishes the
+    /// session up front; otherwise the first `401`/`403` response triggers
+    /// it, and an expired session is re-established the same way.
+    pub fn with_login(
+        base_urls: Vec<String>,
+        login_url: String,
+        credentials: LoginCredentials,
+    ) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wms-client/0.1.0")?;
+
+        Ok(WmsClient {
+            client,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(None).with_login(LoginConfig { login_url, credentials }),
+        })
+    }
+
+    /// Perform the login flow now rather than waiting for a `401`/`403` to
+    /// trigger it.
+    pub async fn login(&self, login_url: &str, credentials: &LoginCredentials) -> Result<(), Box<dyn Error>> {
+        OgcService::login(self, login_url, credentials).await
+    }
+
+    /// Fetch and parse the server's `GetCapabilities` document.
+    ///
+    /// Walks the `<Capability>/<Layer>` tree, resolving CRS/bbox/styles
+    /// inherited from parent layers, so callers can validate a layer name
+    /// and CRS before calling `fetch_map_tile`.
+    pub async fn get_capabilities(&self) -> Result<WmsCapabilities, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.get_capabilities_from(base_url))
+            .await
+    }
+
+    async fn get_capabilities_from(&self, base_url: String) -> Result<WmsCapabilities, Box<dyn Error>> {
+        let url = format!(
+            "{}?SERVICE=WMS&VERSION=1.3.0&REQUEST=GetCapabilities",
+            base_url
+        );
+
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "WMS GetCapabilities request failed with status: {}",
+                response.status()
+            )
+            .into());
+        }
+
+        let body = response.text().await?;
+        parse_wms_capabilities(&body)
+    }
+
+    /// Fetch a map tile from WMS server in the requested `format`, falling back across mirror
+    /// endpoints if one is unreachable or returns an error status.
     pub async fn fetch_map_tile(
         &self,
         layers: &str,
@@ -52,42 +110,34 @@ impl WmsClient {
         srs: &str,
         format: &str,
         transparent: bool,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| {
+                self.fetch_map_tile_from(base_url, layers, bbox, width, height, srs, format, transparent)
+            })
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_map_tile_from(
+        &self,
+        base_url: String,
+        layers: &str,
+        bbox: &str,
+        width: u32,
+        height: u32,
+        srs: &str,
+        format: &str,
+        transparent: bool,
     ) -> Result<Vec<u8>, Box<dyn Error>> {
         // Build base WMS request URL
-        let mut url = format!(
+        let url = format!(
             "{}?SERVICE=WMS&VERSION=1.3.0&REQUEST=GetMap&LAYERS={}&BBOX={}&WIDTH={}&HEIGHT={}&CRS={}&FORMAT={}&TRANSPARENT={}&styles=default",
-            self.base_url, layers, bbox, width, height, srs, format, transparent
+            base_url, layers, bbox, width, height, srs, format, transparent
         );
 
-        // Apply API key authentication if needed
-        let mut final_url = url.clone();
-        if let Some(WmsAuth::ApiKey { param_name, key }) = &self.auth {
-            final_url = format!("{}&{}={}", url, param_name, key);
-        }
-
-        // Build the request with appropriate authentication
-        let mut request = self.client.get(&final_url);
-
-        // Apply authentication if configured
-        if let Some(auth) = &self.auth {
-            match auth {
-                WmsAuth::Basic { username, password } => {
-                    request = request.basic_auth(username, Some(password));
-                }
-                WmsAuth::BearerToken(token) => {
-                    request = request.bearer_auth(token);
-                }
-                WmsAuth::Cookie(cookie_str) => {
-                    request = request.header(header::COOKIE, cookie_str);
-                }
-                WmsAuth::ApiKey { .. } => {
-                    // Already handled in URL construction
-                }
-            }
-        }
-
         // Execute request
-        let response = request.send().await?;
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
 
         // Check for success
         if !response.status().is_success() {
@@ -116,6 +166,11 @@ pub async fn fetch_wms_example() -> Result<(), Box<dyn Error>> {
         None,
     )?;
 
+    // Discover layer names, CRS and styles instead of hand-copying them from
+    // portal documentation.
+    let capabilities = wms_client.get_capabilities().await?;
+    println!("WMS capabilities: {:?}", capabilities);
+
     // Fetch a map tile
     let tile_data = wms_client
         .fetch_map_tile(
@@ -135,6 +190,26 @@ pub async fn fetch_wms_example() -> Result<(), Box<dyn Error>> {
     WmsClient::save_tile_to_file(&tile_data, "map_tile.png")?;
     println!("Tile saved as map_tile.png");
 
+    // Example of a portal that requires logging in before serving requests:
+    // `login` can be called up front; otherwise the first 401/403 triggers
+    // it, and an expired session is re-established the same way.
+    let login_client = WmsClient::with_login(
+        vec!["https://secure-example.com/geoserver/wms".to_string()],
+        "https://secure-example.com/login".to_string(),
+        LoginCredentials::Form(HashMap::from([
+            ("username".to_string(), "demo".to_string()),
+            ("password".to_string(), "demo".to_string()),
+        ])),
+    )?;
+    login_client
+        .login(
+            "https://secure-example.com/login",
+            &LoginCredentials::Form(HashMap::from([
+                ("username".to_string(), "demo".to_string()),
+                ("password".to_string(), "demo".to_string()),
+            ])),
+        )
+        .await?;
+
     Ok(())
 }
-
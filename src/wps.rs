@@ -0,0 +1,795 @@
+use quick_xml::Reader;
+use quick_xml::Writer;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use reqwest::{self, Client};
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::ogc::{self, Auth, AuthLayer, LoginConfig, LoginCredentials, OgcService};
+use crate::resolver::ServiceResolver;
+
+/// Summary of a process as listed in a WPS `GetCapabilities` response.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProcessSummary {
+    pub identifier: String,
+    pub title: String,
+    pub r#abstract: String,
+}
+
+/// Allowed value domain for a WPS literal input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralDataType {
+    /// Any value of the given XML Schema type (e.g. `string`, `double`).
+    AnyValue(String),
+    /// One of a fixed set of allowed values.
+    AllowedValues(Vec<String>),
+}
+
+/// A single typed input accepted by a process, as described by `DescribeProcess`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataInput {
+    pub identifier: String,
+    pub title: String,
+    pub min_occurs: u32,
+    pub max_occurs: u32,
+    pub literal_type: Option<LiteralDataType>,
+    pub complex_mime_types: Vec<String>,
+}
+
+/// A single typed output produced by a process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub identifier: String,
+    pub title: String,
+    pub complex_mime_types: Vec<String>,
+}
+
+/// Full description of a process, as returned by `DescribeProcess`.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessDescription {
+    pub identifier: String,
+    pub title: String,
+    pub inputs: Vec<DataInput>,
+    pub outputs: Vec<Output>,
+}
+
+/// A value bound to a process input when calling `execute`.
+pub enum WpsInput {
+    /// A literal scalar value (number, string, boolean, ...).
+    Literal(String),
+    /// A complex value such as a WKT/GML geometry, tagged with its MIME type.
+    Complex { value: String, mime_type: String },
+}
+
+/// A named output value extracted from an `ExecuteResponse`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecuteOutput {
+    pub identifier: String,
+    pub value: String,
+}
+
+fn local_name(tag: &[u8]) -> &[u8] {
+    match tag.iter().position(|&b| b == b':') {
+        Some(idx) => &tag[idx + 1..],
+        None => tag,
+    }
+}
+
+/// Parse a WPS `minOccurs`/`maxOccurs` attribute value. `maxOccurs` is
+/// routinely `"unbounded"` in `DescribeProcess` responses; represent that as
+/// `u32::MAX` instead of silently clamping an unlimited input to 1.
+fn parse_occurs(value: &str) -> u32 {
+    if value == "unbounded" {
+        u32::MAX
+    } else {
+        value.parse().unwrap_or(1)
+    }
+}
+
+fn read_text(reader: &mut Reader<&[u8]>) -> Result<String, Box<dyn Error>> {
+    let mut buf = Vec::new();
+    let text = match reader.read_event_into(&mut buf)? {
+        Event::Text(t) => t.unescape()?.into_owned(),
+        Event::CData(t) => String::from_utf8(t.into_inner().into_owned())?,
+        _ => String::new(),
+    };
+    Ok(text)
+}
+
+/// Read everything up to (not including) the current element's matching end
+/// tag and re-serialize it verbatim, nested markup included.
+///
+/// Unlike `read_text`, which only captures a single `Event::Text` and comes
+/// back empty the moment a child element appears, this handles `wps:Data`
+/// payloads that carry nested XML/GML (`wps:ComplexData`) rather than plain
+/// text.
+fn read_inner_xml(reader: &mut Reader<&[u8]>) -> Result<String, Box<dyn Error>> {
+    let mut writer = Writer::new(Vec::new());
+    let mut buf = Vec::new();
+    let mut depth = 0u32;
+
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::End(_) if depth == 0 => break,
+            Event::Eof => break,
+            Event::Start(_) => {
+                depth += 1;
+                writer.write_event(&event)?;
+            }
+            Event::End(_) => {
+                depth -= 1;
+                writer.write_event(&event)?;
+            }
+            _ => writer.write_event(&event)?,
+        }
+        buf.clear();
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// Client for accessing Web Processing Service (WPS) servers.
+pub struct WpsClient {
+    client: Client,
+    resolver: ServiceResolver,
+    auth: AuthLayer,
+}
+
+impl OgcService for WpsClient {
+    fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    fn auth_layer(&self) -> &AuthLayer {
+        &self.auth
+    }
+}
+
+impl WpsClient {
+    /// Create a new WPS client over a single endpoint
+    pub fn new(base_url: &str, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        Self::with_mirrors(vec![base_url.to_string()], auth)
+    }
+
+    /// Create a new WPS client backed by a prioritized list of mirror
+    /// endpoints. Requests try each candidate in order, falling back to the
+    /// next on a connection error or non-success response.
+    pub fn with_mirrors(base_urls: Vec<String>, auth: Option<Auth>) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wps-client/0.1.0")?;
+
+        Ok(WpsClient {
+            client,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(auth),
+        })
+    }
+
+    /// Create a new WPS client that authenticates via a login endpoint
+    /// instead of a pre-shared credential. Calling `login` establishes the
+    /// session up front; otherwise the first `401`/`403` response triggers
+    /// it, and an expired session is re-established the same way.
+    pub fn with_login(
+        base_urls: Vec<String>,
+        login_url: String,
+        credentials: LoginCredentials,
+    ) -> Result<Self, Box<dyn Error>> {
+        let client = ogc::build_http_client("rust-wps-client/0.1.0")?;
+
+        Ok(WpsClient {
+            client,
+            resolver: ServiceResolver::new(base_urls),
+            auth: AuthLayer::new(None).with_login(LoginConfig { login_url, credentials }),
+        })
+    }
+
+    /// Perform the login flow now rather than waiting for a `401`/`403` to
+    /// trigger it.
+    pub async fn login(&self, login_url: &str, credentials: &LoginCredentials) -> Result<(), Box<dyn Error>> {
+        OgcService::login(self, login_url, credentials).await
+    }
+
+    /// Fetch and parse the server's `GetCapabilities` document into a list of processes.
+    pub async fn get_capabilities(&self) -> Result<Vec<ProcessSummary>, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.get_capabilities_from(base_url))
+            .await
+    }
+
+    async fn get_capabilities_from(&self, base_url: String) -> Result<Vec<ProcessSummary>, Box<dyn Error>> {
+        let url = format!(
+            "{}?SERVICE=WPS&VERSION=1.0.0&REQUEST=GetCapabilities",
+            base_url
+        );
+
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "WPS GetCapabilities request failed with status: {}",
+                response.status()
+            )
+            .into());
+        }
+
+        let body = response.text().await?;
+        parse_process_summaries(&body)
+    }
+
+    /// Fetch and parse a process's `DescribeProcess` document.
+    pub async fn describe_process(
+        &self,
+        identifier: &str,
+    ) -> Result<ProcessDescription, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.describe_process_from(base_url, identifier))
+            .await
+    }
+
+    async fn describe_process_from(
+        &self,
+        base_url: String,
+        identifier: &str,
+    ) -> Result<ProcessDescription, Box<dyn Error>> {
+        let url = format!(
+            "{}?SERVICE=WPS&VERSION=1.0.0&REQUEST=DescribeProcess&IDENTIFIER={}",
+            base_url, identifier
+        );
+
+        let response = self.send_with_reauth(self.authenticated_get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "WPS DescribeProcess request failed with status: {}",
+                response.status()
+            )
+            .into());
+        }
+
+        let body = response.text().await?;
+        parse_process_description(&body)
+    }
+
+    /// Run a process, submitting `inputs` either as KVP (small literal-only
+    /// requests) or as a POSTed WPS 1.0.0 `Execute` XML body (when any input
+    /// is complex), and return the parsed output values.
+    pub async fn execute(
+        &self,
+        identifier: &str,
+        inputs: &[(&str, WpsInput)],
+    ) -> Result<Vec<ExecuteOutput>, Box<dyn Error>> {
+        self.resolver
+            .try_each(|base_url| self.execute_from(base_url, identifier, inputs))
+            .await
+    }
+
+    async fn execute_from(
+        &self,
+        base_url: String,
+        identifier: &str,
+        inputs: &[(&str, WpsInput)],
+    ) -> Result<Vec<ExecuteOutput>, Box<dyn Error>> {
+        let has_complex_input = inputs
+            .iter()
+            .any(|(_, input)| matches!(input, WpsInput::Complex { .. }));
+
+        let response = if has_complex_input {
+            let body = build_execute_request(identifier, inputs);
+            self.send_with_reauth(self.authenticated_post(&base_url, "text/xml", body))
+                .await?
+        } else {
+            let mut url = format!(
+                "{}?SERVICE=WPS&VERSION=1.0.0&REQUEST=Execute&IDENTIFIER={}",
+                base_url, identifier
+            );
+            if !inputs.is_empty() {
+                let data_inputs = inputs
+                    .iter()
+                    .map(|(id, input)| match input {
+                        WpsInput::Literal(value) => {
+                            format!("{}={}", percent_encode_kvp_value(id), percent_encode_kvp_value(value))
+                        }
+                        WpsInput::Complex { value, .. } => {
+                            format!("{}={}", percent_encode_kvp_value(id), percent_encode_kvp_value(value))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";");
+                url.push_str(&format!("&DataInputs={}", data_inputs));
+            }
+            self.send_with_reauth(self.authenticated_get(&url)).await?
+        };
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "WPS Execute request failed with status: {}",
+                response.status()
+            )
+            .into());
+        }
+
+        let body = response.text().await?;
+        parse_execute_response(&body)
+    }
+}
+
+/// Demonstrates the WPS client against a public geoprocessing service:
+/// list its processes, describe one, then run it.
+pub async fn fetch_wps_example() -> Result<(), Box<dyn Error>> {
+    let wps_client = WpsClient::new("https://www.geodatenportal.sachsen-anhalt.de/arcgis/services/LAGB/LAGB_Geophysik_G1_OpenData/MapServer/WFSServer/WpsServer", None)?;
+
+    let processes = wps_client.get_capabilities().await?;
+    println!("WPS processes: {:?}", processes);
+
+    if let Some(process) = processes.first() {
+        let description = wps_client.describe_process(&process.identifier).await?;
+        println!("Process description: {:?}", description);
+
+        // A literal-only request, dispatched as KVP/GET.
+        let outputs = wps_client
+            .execute(
+                &process.identifier,
+                &[("input", WpsInput::Literal("1".to_string()))],
+            )
+            .await?;
+        println!("Execute outputs: {:?}", outputs);
+
+        // A complex input (e.g. a WKT/GML geometry), dispatched as a
+        // POSTed Execute XML body instead of KVP.
+        let buffered = wps_client
+            .execute(
+                &process.identifier,
+                &[(
+                    "geometry",
+                    WpsInput::Complex {
+                        value: "POLYGON((0 0, 1 0, 1 1, 0 0))".to_string(),
+                        mime_type: "application/wkt".to_string(),
+                    },
+                )],
+            )
+            .await?;
+        println!("Execute outputs (complex input): {:?}", buffered);
+    }
+
+    // Example of a portal that requires logging in before serving requests:
+    // `login` can be called up front; otherwise the first 401/403 triggers
+    // it, and an expired session is re-established the same way.
+    let login_client = WpsClient::with_login(
+        vec!["https://secure-example.com/wps".to_string()],
+        "https://secure-example.com/login".to_string(),
+        LoginCredentials::Form(HashMap::from([
+            ("username".to_string(), "demo".to_string()),
+            ("password".to_string(), "demo".to_string()),
+        ])),
+    )?;
+    login_client
+        .login(
+            "https://secure-example.com/login",
+            &LoginCredentials::Form(HashMap::from([
+                ("username".to_string(), "demo".to_string()),
+                ("password".to_string(), "demo".to_string()),
+            ])),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Percent-encode `value` for safe inclusion in a KVP `Execute` request's
+/// `DataInputs` query parameter, whose own syntax uses `=`/`;` as
+/// structural separators between inputs (`id1=value1;id2=value2`). Without
+/// this, an input id/value containing `&`, `;` or `=` would silently
+/// corrupt the query instead of erroring.
+fn percent_encode_kvp_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Build a WPS 1.0.0 `Execute` request body for POSTing.
+///
+/// Identifiers and literal values are escaped before interpolation, since
+/// they're arbitrary text content/attribute values (a string parameter
+/// containing `&`, `<` or `>` would otherwise produce malformed XML).
+/// `ComplexData`'s `value` is deliberately left unescaped: it's raw
+/// XML/GML meant to be embedded as markup, not text content.
+fn build_execute_request(identifier: &str, inputs: &[(&str, WpsInput)]) -> String {
+    let mut data_inputs = String::new();
+    for (id, input) in inputs {
+        data_inputs.push_str("<wps:Input>");
+        data_inputs.push_str(&format!("<ows:Identifier>{}</ows:Identifier>", escape(id)));
+        match input {
+            WpsInput::Literal(value) => {
+                data_inputs.push_str(&format!(
+                    "<wps:Data><wps:LiteralData>{}</wps:LiteralData></wps:Data>",
+                    escape(value)
+                ));
+            }
+            WpsInput::Complex { value, mime_type } => {
+                data_inputs.push_str(&format!(
+                    "<wps:Data><wps:ComplexData mimeType=\"{}\">{}</wps:ComplexData></wps:Data>",
+                    escape(mime_type),
+                    value
+                ));
+            }
+        }
+        data_inputs.push_str("</wps:Input>");
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<wps:Execute service=\"WPS\" version=\"1.0.0\" \
+xmlns:wps=\"http://www.opengis.net/wps/1.0.0\" \
+xmlns:ows=\"http://www.opengis.net/ows/1.1\">\
+<ows:Identifier>{}</ows:Identifier>\
+<wps:DataInputs>{}</wps:DataInputs>\
+</wps:Execute>",
+        escape(identifier),
+        data_inputs
+    )
+}
+
+fn parse_process_summaries(xml: &str) -> Result<Vec<ProcessSummary>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut processes = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if local_name(e.name().as_ref()) == b"Process" => {
+                processes.push(parse_process_summary(&mut reader)?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(processes)
+}
+
+fn parse_process_summary(reader: &mut Reader<&[u8]>) -> Result<ProcessSummary, Box<dyn Error>> {
+    let mut summary = ProcessSummary::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Identifier" => summary.identifier = read_text(reader)?,
+                b"Title" => summary.title = read_text(reader)?,
+                b"Abstract" => summary.r#abstract = read_text(reader)?,
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"Process" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(summary)
+}
+
+fn parse_process_description(xml: &str) -> Result<ProcessDescription, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut description = ProcessDescription::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Identifier" if description.identifier.is_empty() => {
+                    description.identifier = read_text(&mut reader)?;
+                }
+                b"Title" if description.title.is_empty() => {
+                    description.title = read_text(&mut reader)?;
+                }
+                b"Input" => {
+                    let min_occurs = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == b"minOccurs")
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| parse_occurs(&v))
+                        .unwrap_or(1);
+                    let max_occurs = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == b"maxOccurs")
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| parse_occurs(&v))
+                        .unwrap_or(1);
+                    description
+                        .inputs
+                        .push(parse_data_input(&mut reader, min_occurs, max_occurs)?);
+                }
+                b"Output" => description.outputs.push(parse_output(&mut reader)?),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(description)
+}
+
+fn parse_data_input(
+    reader: &mut Reader<&[u8]>,
+    min_occurs: u32,
+    max_occurs: u32,
+) -> Result<DataInput, Box<dyn Error>> {
+    let mut input = DataInput {
+        identifier: String::new(),
+        title: String::new(),
+        min_occurs,
+        max_occurs,
+        literal_type: None,
+        complex_mime_types: Vec::new(),
+    };
+
+    let mut data_type: Option<String> = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                let name = local_name(e.name().as_ref()).to_vec();
+                match name.as_slice() {
+                    b"Identifier" => input.identifier = read_text(reader)?,
+                    b"Title" => input.title = read_text(reader)?,
+                    b"MimeType" => input.complex_mime_types.push(read_text(reader)?),
+                    b"DataType" => {
+                        let text = read_text(reader)?;
+                        data_type = Some(text.rsplit(':').next().unwrap_or(&text).to_string());
+                    }
+                    b"AnyValue" => {
+                        let data_type = data_type.clone().unwrap_or_else(|| "string".to_string());
+                        input.literal_type = Some(LiteralDataType::AnyValue(data_type))
+                    }
+                    b"AllowedValues" => {
+                        input.literal_type =
+                            Some(LiteralDataType::AllowedValues(parse_allowed_values(reader)?))
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if local_name(e.name().as_ref()) == b"Input" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(input)
+}
+
+fn parse_allowed_values(reader: &mut Reader<&[u8]>) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut values = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if local_name(e.name().as_ref()) == b"Value" => {
+                values.push(read_text(reader)?);
+            }
+            Event::End(e) if local_name(e.name().as_ref()) == b"AllowedValues" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(values)
+}
+
+fn parse_output(reader: &mut Reader<&[u8]>) -> Result<Output, Box<dyn Error>> {
+    let mut output = Output {
+        identifier: String::new(),
+        title: String::new(),
+        complex_mime_types: Vec::new(),
+    };
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Identifier" => output.identifier = read_text(reader)?,
+                b"Title" => output.title = read_text(reader)?,
+                b"MimeType" => output.complex_mime_types.push(read_text(reader)?),
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"Output" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(output)
+}
+
+fn parse_execute_response(xml: &str) -> Result<Vec<ExecuteOutput>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut outputs = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if local_name(e.name().as_ref()) == b"Output" => {
+                outputs.push(parse_execute_output(&mut reader)?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(outputs)
+}
+
+fn parse_execute_output(reader: &mut Reader<&[u8]>) -> Result<ExecuteOutput, Box<dyn Error>> {
+    let mut identifier = String::new();
+    let mut value = String::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match local_name(e.name().as_ref()) {
+                b"Identifier" => identifier = read_text(reader)?,
+                b"LiteralData" => value = read_text(reader)?,
+                b"ComplexData" => value = read_inner_xml(reader)?,
+                _ => {}
+            },
+            Event::End(e) if local_name(e.name().as_ref()) == b"Output" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(ExecuteOutput { identifier, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_process_summaries_reads_identifier_title_and_abstract() {
+        let xml = r#"<wps:Capabilities>
+            <wps:ProcessOfferings>
+                <wps:Process>
+                    <ows:Identifier>buffer</ows:Identifier>
+                    <ows:Title>Buffer</ows:Title>
+                    <ows:Abstract>Buffers a geometry.</ows:Abstract>
+                </wps:Process>
+            </wps:ProcessOfferings>
+        </wps:Capabilities>"#;
+
+        let processes = parse_process_summaries(xml).unwrap();
+
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].identifier, "buffer");
+        assert_eq!(processes[0].title, "Buffer");
+        assert_eq!(processes[0].r#abstract, "Buffers a geometry.");
+    }
+
+    #[test]
+    fn parse_process_description_reads_min_max_occurs_from_input_attributes() {
+        let xml = r#"<wps:ProcessDescriptions>
+            <ProcessDescription>
+                <ows:Identifier>buffer</ows:Identifier>
+                <ows:Title>Buffer</ows:Title>
+                <DataInputs>
+                    <Input minOccurs="0" maxOccurs="2">
+                        <ows:Identifier>distance</ows:Identifier>
+                        <ows:Title>Distance</ows:Title>
+                    </Input>
+                    <Input minOccurs="1" maxOccurs="1">
+                        <ows:Identifier>geometry</ows:Identifier>
+                        <ows:Title>Geometry</ows:Title>
+                    </Input>
+                </DataInputs>
+            </ProcessDescription>
+        </wps:ProcessDescriptions>"#;
+
+        let description = parse_process_description(xml).unwrap();
+
+        assert_eq!(description.inputs.len(), 2);
+        assert_eq!(description.inputs[0].identifier, "distance");
+        assert_eq!(description.inputs[0].min_occurs, 0);
+        assert_eq!(description.inputs[0].max_occurs, 2);
+        assert_eq!(description.inputs[1].min_occurs, 1);
+        assert_eq!(description.inputs[1].max_occurs, 1);
+    }
+
+    #[test]
+    fn parse_process_description_reads_unbounded_max_occurs() {
+        let xml = r#"<wps:ProcessDescriptions>
+            <ProcessDescription>
+                <ows:Identifier>buffer</ows:Identifier>
+                <DataInputs>
+                    <Input minOccurs="1" maxOccurs="unbounded">
+                        <ows:Identifier>points</ows:Identifier>
+                    </Input>
+                </DataInputs>
+            </ProcessDescription>
+        </wps:ProcessDescriptions>"#;
+
+        let description = parse_process_description(xml).unwrap();
+
+        assert_eq!(description.inputs[0].max_occurs, u32::MAX);
+    }
+
+    #[test]
+    fn parse_process_description_defaults_occurs_to_one_when_absent() {
+        let xml = r#"<wps:ProcessDescriptions>
+            <ProcessDescription>
+                <ows:Identifier>buffer</ows:Identifier>
+                <DataInputs>
+                    <Input>
+                        <ows:Identifier>distance</ows:Identifier>
+                    </Input>
+                </DataInputs>
+            </ProcessDescription>
+        </wps:ProcessDescriptions>"#;
+
+        let description = parse_process_description(xml).unwrap();
+
+        assert_eq!(description.inputs[0].min_occurs, 1);
+        assert_eq!(description.inputs[0].max_occurs, 1);
+    }
+
+    #[test]
+    fn parse_execute_response_reads_output_values() {
+        let xml = r#"<wps:ExecuteResponse>
+            <wps:ProcessOutputs>
+                <wps:Output>
+                    <ows:Identifier>result</ows:Identifier>
+                    <wps:Data><wps:LiteralData>42</wps:LiteralData></wps:Data>
+                </wps:Output>
+            </wps:ProcessOutputs>
+        </wps:ExecuteResponse>"#;
+
+        let outputs = parse_execute_response(xml).unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].identifier, "result");
+        assert_eq!(outputs[0].value, "42");
+    }
+
+    #[test]
+    fn parse_execute_response_reads_nested_complex_data_output() {
+        let xml = r#"<wps:ExecuteResponse>
+            <wps:ProcessOutputs>
+                <wps:Output>
+                    <ows:Identifier>buffered</ows:Identifier>
+                    <wps:Data><wps:ComplexData mimeType="application/gml+xml">
+                        <gml:Polygon><gml:exterior><gml:LinearRing>
+                            <gml:posList>0 0 1 0 1 1 0 0</gml:posList>
+                        </gml:LinearRing></gml:exterior></gml:Polygon>
+                    </wps:ComplexData></wps:Data>
+                </wps:Output>
+            </wps:ProcessOutputs>
+        </wps:ExecuteResponse>"#;
+
+        let outputs = parse_execute_response(xml).unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].identifier, "buffered");
+        assert!(outputs[0].value.contains("<gml:Polygon>"));
+        assert!(outputs[0].value.contains("<gml:posList>0 0 1 0 1 1 0 0</gml:posList>"));
+    }
+
+    #[test]
+    fn build_execute_request_escapes_literal_values() {
+        let inputs = vec![("name", WpsInput::Literal("Tom & Jerry <3".to_string()))];
+
+        let body = build_execute_request("echo", &inputs);
+
+        assert!(body.contains("Tom &amp; Jerry &lt;3"));
+        assert!(!body.contains("Tom & Jerry <3"));
+    }
+
+    #[test]
+    fn percent_encode_kvp_value_escapes_structural_characters() {
+        assert_eq!(percent_encode_kvp_value("a&b;c=d"), "a%26b%3Bc%3Dd");
+        assert_eq!(percent_encode_kvp_value("plain-value_1.0~"), "plain-value_1.0~");
+    }
+}